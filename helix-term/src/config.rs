@@ -0,0 +1,130 @@
+//! The top-level `Config` assembled from a user's `config.toml` layered
+//! over Helix's built-in defaults (see [`crate::config_merge`]), validated
+//! against the generated JSON Schema (see [`crate::config_validation`])
+//! before it's deserialized, so an unknown key or wrong-typed value is
+//! reported with a line number instead of surfacing as a generic serde
+//! error or, worse, silently being ignored.
+
+use std::fmt;
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config_merge::merge_layered_config;
+use crate::config_validation::{validate_merged_config, ValidationError};
+
+/// The `config.toml` shape schemars derives the generated JSON Schema from.
+/// Kept separate from [`Config`] itself so the merged, validated raw value
+/// can be deserialized in one step once it's known to be schema-clean.
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct ConfigRaw {
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub editor: toml::Value,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Config {
+    pub theme: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ConfigLoadError {
+    Io(std::io::Error),
+    /// The merged `config.toml` failed schema validation; see each
+    /// [`ValidationError`] for the offending line and layer.
+    Validation(Vec<ValidationError>),
+    Parse(toml::de::Error),
+}
+
+impl fmt::Display for ConfigLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Validation(errors) => {
+                for error in errors {
+                    writeln!(f, "{error}")?;
+                }
+                Ok(())
+            }
+            Self::Parse(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl Config {
+    /// Read `user_config_path`, merge it over `default_source` and validate
+    /// the result against `schema` before deserializing it. A missing user
+    /// config file is treated as empty rather than an error, matching
+    /// Helix's usual "defaults are enough to start" behavior.
+    pub fn load(
+        schema: &serde_json::Value,
+        default_source: &str,
+        user_config_path: &Path,
+    ) -> Result<Config, ConfigLoadError> {
+        let user_source = match std::fs::read_to_string(user_config_path) {
+            Ok(source) => source,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(ConfigLoadError::Io(err)),
+        };
+
+        validate_merged_config(schema, default_source, &user_source)
+            .map_err(ConfigLoadError::Validation)?;
+
+        let merged = merge_layered_config(
+            toml::from_str(default_source).map_err(ConfigLoadError::Parse)?,
+            toml::from_str(&user_source).map_err(ConfigLoadError::Parse)?,
+        );
+        let raw: ConfigRaw = merged.try_into().map_err(ConfigLoadError::Parse)?;
+
+        Ok(Config { theme: raw.theme })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": { "theme": { "type": "string" } },
+            "additionalProperties": false,
+        })
+    }
+
+    #[test]
+    fn load_merges_user_config_over_defaults() {
+        let dir = std::env::temp_dir().join("helix-config-load-test-merges");
+        std::fs::create_dir_all(&dir).unwrap();
+        let user_config_path = dir.join("config.toml");
+        std::fs::write(&user_config_path, "theme = \"my-theme\"\n").unwrap();
+
+        let config = Config::load(&schema(), "theme = \"default\"\n", &user_config_path).unwrap();
+
+        assert_eq!(config.theme.as_deref(), Some("my-theme"));
+    }
+
+    #[test]
+    fn load_treats_a_missing_user_config_as_empty() {
+        let user_config_path = std::env::temp_dir().join("helix-config-load-test-does-not-exist.toml");
+        let _ = std::fs::remove_file(&user_config_path);
+
+        let config = Config::load(&schema(), "theme = \"default\"\n", &user_config_path).unwrap();
+
+        assert_eq!(config.theme.as_deref(), Some("default"));
+    }
+
+    #[test]
+    fn load_rejects_a_user_config_that_fails_the_schema() {
+        let dir = std::env::temp_dir().join("helix-config-load-test-rejects");
+        std::fs::create_dir_all(&dir).unwrap();
+        let user_config_path = dir.join("config.toml");
+        std::fs::write(&user_config_path, "theme = 1\n").unwrap();
+
+        let err = Config::load(&schema(), "theme = \"default\"\n", &user_config_path).unwrap_err();
+
+        assert!(matches!(err, ConfigLoadError::Validation(_)));
+    }
+}
@@ -0,0 +1,142 @@
+//! Layered config merge: built-in defaults, then an optional
+//! `[platform.<os>]` override table, then the user's own `config.toml`,
+//! merging tables recursively so a key set at a shallower layer only
+//! overrides that one key rather than the whole surrounding table.
+
+use toml::Value;
+
+/// The `cfg!(target_os)` key a platform's overrides live under, e.g. in
+/// `[platform.macos]` / `[platform.linux]` / `[platform.windows]`. Every
+/// other OS uses the `linux` table.
+fn current_platform_key() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "macos"
+    } else if cfg!(target_os = "windows") {
+        "windows"
+    } else {
+        "linux"
+    }
+}
+
+/// Merge `defaults` (the built-in config), any `[platform.<os>]` override
+/// table present in either layer, and `user`'s own config.toml, in that
+/// order, so the user file always has the final say. The `platform` table
+/// itself is stripped from the result before it's returned — it's been
+/// folded into the rest of the document by this point.
+pub fn merge_layered_config(defaults: Value, user: Value) -> Value {
+    let platform_key = current_platform_key();
+
+    let mut merged = defaults.clone();
+    if let Some(overrides) = take_platform_table(&defaults, platform_key) {
+        deep_merge(&mut merged, overrides);
+    }
+    if let Some(overrides) = take_platform_table(&user, platform_key) {
+        deep_merge(&mut merged, overrides);
+    }
+    deep_merge(&mut merged, user);
+
+    if let Value::Table(table) = &mut merged {
+        table.remove("platform");
+    }
+
+    merged
+}
+
+fn take_platform_table(value: &Value, platform_key: &str) -> Option<Value> {
+    value.get("platform")?.get(platform_key).cloned()
+}
+
+/// Recursively merge `overlay` into `base`: matching tables merge key by
+/// key; any other value (including arrays, which are replaced rather than
+/// concatenated) overwrites `base` wholesale.
+fn deep_merge(base: &mut Value, overlay: Value) {
+    match overlay {
+        Value::Table(overlay_table) => {
+            if let Value::Table(base_table) = base {
+                for (key, overlay_value) in overlay_table {
+                    match base_table.get_mut(&key) {
+                        Some(base_value) => deep_merge(base_value, overlay_value),
+                        None => {
+                            base_table.insert(key, overlay_value);
+                        }
+                    }
+                }
+            } else {
+                *base = Value::Table(overlay_table);
+            }
+        }
+        other => *base = other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toml(source: &str) -> Value {
+        source.parse().unwrap()
+    }
+
+    #[test]
+    fn deep_merge_only_overrides_the_keys_the_overlay_sets() {
+        let mut base = toml("[editor]\nscrolloff = 5\nline-number = \"absolute\"\n");
+        let overlay = toml("[editor]\nscrolloff = 3\n");
+
+        deep_merge(&mut base, overlay);
+
+        assert_eq!(base["editor"]["scrolloff"].as_integer(), Some(3));
+        assert_eq!(base["editor"]["line-number"].as_str(), Some("absolute"));
+    }
+
+    #[test]
+    fn deep_merge_replaces_arrays_wholesale() {
+        let mut base = toml("list = [1, 2, 3]\n");
+        let overlay = toml("list = [4]\n");
+
+        deep_merge(&mut base, overlay);
+
+        assert_eq!(base["list"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn user_overrides_win_over_platform_which_wins_over_defaults() {
+        let platform = current_platform_key();
+        let defaults = toml(&format!(
+            "theme = \"base\"\n\
+             [editor]\n\
+             scrolloff = 0\n\
+             [platform.{platform}]\n\
+             theme = \"platform-default\"\n\
+             [platform.{platform}.editor]\n\
+             line-number = \"absolute\"\n"
+        ));
+        let user = toml(
+            "[editor]\n\
+             line-number = \"relative\"\n",
+        );
+
+        let merged = merge_layered_config(defaults, user);
+
+        // default-layer platform override applies when the user doesn't
+        // set `theme` at all
+        assert_eq!(merged["theme"].as_str(), Some("platform-default"));
+        // untouched default survives
+        assert_eq!(merged["editor"]["scrolloff"].as_integer(), Some(0));
+        // user-set key wins over the platform override of the same key
+        assert_eq!(merged["editor"]["line-number"].as_str(), Some("relative"));
+        // the platform table itself doesn't leak into the merged result
+        assert!(merged.get("platform").is_none());
+    }
+
+    #[test]
+    fn users_own_platform_override_wins_over_the_default_ones() {
+        let platform = current_platform_key();
+        let defaults = toml(&format!("[platform.{platform}]\ntheme = \"default-{platform}\"\n"));
+        let user = toml(&format!("[platform.{platform}]\ntheme = \"my-{platform}\"\n"));
+
+        let merged = merge_layered_config(defaults, user);
+        let expected_theme = format!("my-{platform}");
+
+        assert_eq!(merged["theme"].as_str(), Some(expected_theme.as_str()));
+    }
+}
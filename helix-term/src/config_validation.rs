@@ -0,0 +1,327 @@
+//! Validates `config.toml` / `languages.toml` against the JSON schemas
+//! handed out under `contrib/schemas`, so Helix catches the same mistakes
+//! (unknown keys, wrong value types) with a precise line instead of a
+//! generic serde error.
+//!
+//! The schema passed in is the same `serde_json::Value` that
+//! `xtask::schemagen::generate_config_schema` / `generate_languages_schema`
+//! produce. [`crate::config::Config::load`] calls [`validate_merged_config`]
+//! before deserializing a user's `config.toml`; `hx --health` calls it again
+//! through [`crate::health::check_config`], which returns the same list of
+//! failures as a plain `Vec` rather than as a load error.
+
+use std::fmt;
+
+use jsonschema::{Draft, JSONSchema};
+use toml_edit::ImDocument;
+
+use crate::config_merge::merge_layered_config;
+
+/// One validation failure, with enough context to point at the offending
+/// line in the source TOML file.
+#[derive(Debug)]
+pub struct ValidationError {
+    /// JSON pointer into the document, e.g. `/editor/scrolloff`.
+    pub instance_path: String,
+    /// The JSON Schema keyword that rejected the value, e.g. `type` or
+    /// `additionalProperties`.
+    pub keyword: String,
+    pub message: String,
+    /// 1-based line number in the source TOML, when it could be recovered.
+    pub line: Option<usize>,
+    /// Which layer the line number above was recovered from (see
+    /// [`validate_merged_config`]); [`validate_toml`] always names its one
+    /// source `config.toml`. `None` whenever `line` is `None`.
+    pub layer: Option<String>,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.layer, self.line) {
+            (Some(layer), Some(line)) => {
+                write!(f, "{} ({layer}, line {line}): {}", self.instance_path, self.message)
+            }
+            (None, Some(line)) => {
+                write!(f, "{} (line {line}): {}", self.instance_path, self.message)
+            }
+            _ => write!(f, "{}: {}", self.instance_path, self.message),
+        }
+    }
+}
+
+/// One named TOML source to search for a pointer's line number, in
+/// priority order (most specific first).
+struct ConfigLayer<'a> {
+    name: &'a str,
+    source: &'a str,
+}
+
+/// Compile `schema` and validate `toml_source` against it, returning every
+/// failure found rather than stopping at the first one.
+pub fn validate_toml(
+    schema: &serde_json::Value,
+    toml_source: &str,
+) -> Result<(), Vec<ValidationError>> {
+    let document: toml::Value = toml::from_str(toml_source).map_err(|err| {
+        vec![ValidationError {
+            instance_path: "/".to_string(),
+            keyword: "parse".to_string(),
+            message: err.to_string(),
+            line: None,
+            layer: None,
+        }]
+    })?;
+    let instance = serde_json::to_value(&document).expect("TOML value is representable as JSON");
+
+    validate_instance(
+        schema,
+        &instance,
+        &[ConfigLayer {
+            name: "config.toml",
+            source: toml_source,
+        }],
+    )
+}
+
+/// Merge `defaults_source` and `user_source` (see
+/// [`crate::config_merge::merge_layered_config`]) and validate the result,
+/// attributing each error to whichever layer actually sets the offending
+/// key — a merged document has no single source string to recover a line
+/// number from, so every candidate layer is checked in priority order
+/// (user's file first, falling back to the built-in defaults).
+pub fn validate_merged_config(
+    schema: &serde_json::Value,
+    defaults_source: &str,
+    user_source: &str,
+) -> Result<(), Vec<ValidationError>> {
+    let parse = |source: &str| -> Result<toml::Value, Vec<ValidationError>> {
+        toml::from_str(source).map_err(|err| {
+            vec![ValidationError {
+                instance_path: "/".to_string(),
+                keyword: "parse".to_string(),
+                message: err.to_string(),
+                line: None,
+                layer: None,
+            }]
+        })
+    };
+
+    let merged = merge_layered_config(parse(defaults_source)?, parse(user_source)?);
+    let instance = serde_json::to_value(&merged).expect("TOML value is representable as JSON");
+
+    validate_instance(
+        schema,
+        &instance,
+        &[
+            ConfigLayer {
+                name: "config.toml",
+                source: user_source,
+            },
+            ConfigLayer {
+                name: "built-in defaults",
+                source: defaults_source,
+            },
+        ],
+    )
+}
+
+fn validate_instance(
+    schema: &serde_json::Value,
+    instance: &serde_json::Value,
+    layers: &[ConfigLayer],
+) -> Result<(), Vec<ValidationError>> {
+    let compiled = JSONSchema::options()
+        .with_draft(Draft::Draft202012)
+        .compile(schema)
+        .expect("generated schemas must themselves be valid JSON Schema");
+
+    let Err(errors) = compiled.validate(instance) else {
+        return Ok(());
+    };
+
+    // `ImDocument` keeps the spans of the source text; `DocumentMut` drops them.
+    let edit_docs: Vec<(&str, Option<ImDocument<&str>>)> = layers
+        .iter()
+        .map(|layer| (layer.name, ImDocument::parse(layer.source).ok()))
+        .collect();
+
+    Err(errors
+        .map(|err| {
+            let instance_path = err.instance_path.to_string();
+            let keyword = err
+                .schema_path
+                .to_string()
+                .rsplit('/')
+                .find(|s| !s.chars().all(|c| c.is_ascii_digit()))
+                .unwrap_or("unknown")
+                .to_string();
+
+            let (line, layer) = edit_docs
+                .iter()
+                .find_map(|(name, doc)| {
+                    let line = line_for_pointer(doc.as_ref()?, &instance_path)?;
+                    Some((line, name.to_string()))
+                })
+                .map_or((None, None), |(line, name)| (Some(line), Some(name)));
+
+            ValidationError {
+                message: err.to_string(),
+                instance_path,
+                keyword,
+                line,
+                layer,
+            }
+        })
+        .collect())
+}
+
+/// Walk a TOML document by the `/`-separated segments of a JSON pointer
+/// (e.g. `/editor/scrolloff`) and return the line the final key sits on, if
+/// every segment resolves to a table key. A segment can also cross a
+/// `[[language]]`/`[[grammar]]`-style array of tables: the array's key is
+/// followed by a numeric index (`/language/0/name`) selecting which table
+/// in the array the remaining segments walk into.
+fn line_for_pointer(doc: &ImDocument<&str>, pointer: &str) -> Option<usize> {
+    let mut segments = pointer.split('/').filter(|s| !s.is_empty()).peekable();
+    let mut table = doc.as_table();
+    let mut key_span = None;
+
+    while let Some(segment) = segments.next() {
+        let (key, item) = table.get_key_value(segment)?;
+        key_span = key.span();
+
+        if segments.peek().is_none() {
+            break;
+        }
+
+        if let Some(array) = item.as_array_of_tables() {
+            let index: usize = segments.next()?.parse().ok()?;
+            let entry = array.get(index)?;
+            key_span = entry.span();
+            if segments.peek().is_none() {
+                break;
+            }
+            table = entry;
+        } else {
+            table = item.as_table()?;
+        }
+    }
+
+    key_span.map(|span| doc.raw()[..span.start].matches('\n').count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scrolloff_schema() -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "editor": {
+                    "type": "object",
+                    "properties": { "scrolloff": { "type": "integer" } },
+                    "additionalProperties": false,
+                },
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    #[test]
+    fn validate_toml_reports_line_and_keyword() {
+        let source = "[editor]\n\nscrolloff = \"five\"\n";
+        let errors = validate_toml(&scrolloff_schema(), source).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path, "/editor/scrolloff");
+        assert_eq!(errors[0].keyword, "type");
+        assert_eq!(errors[0].line, Some(3));
+    }
+
+    #[test]
+    fn validate_toml_catches_unknown_keys() {
+        let source = "[editor]\nunknown-key = 1\n";
+        let errors = validate_toml(&scrolloff_schema(), source).unwrap_err();
+
+        assert_eq!(errors[0].keyword, "additionalProperties");
+    }
+
+    #[test]
+    fn validate_toml_reports_a_line_inside_an_array_of_tables() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "language": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "auto-format": { "type": "boolean" },
+                        },
+                        "additionalProperties": false,
+                    },
+                },
+            },
+            "additionalProperties": false,
+        });
+        let source = "[[language]]\n\
+                       name = \"rust\"\n\
+                       \n\
+                       [[language]]\n\
+                       name = \"toml\"\n\
+                       auto-format = \"yes\"\n";
+
+        let errors = validate_toml(&schema, source).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path, "/language/1/auto-format");
+        assert_eq!(errors[0].line, Some(6));
+    }
+
+    #[test]
+    fn validate_merged_config_attributes_error_to_the_layer_that_set_it() {
+        let defaults = "[editor]\nscrolloff = 5\n";
+        let user = "theme = \"dark\"\n";
+
+        // `editor.scrolloff` only comes from defaults here, so a bogus
+        // override elsewhere in the user file should still resolve a line
+        // from the *user* source, not silently fall back to defaults.
+        let bad_user = "theme = 1\n";
+        let editor_schema = scrolloff_schema()["properties"]["editor"].clone();
+        let errors = validate_merged_config(
+            &json!({
+                "type": "object",
+                "properties": {
+                    "theme": { "type": "string" },
+                    "editor": editor_schema,
+                },
+                "additionalProperties": false,
+            }),
+            defaults,
+            bad_user,
+        )
+        .unwrap_err();
+
+        assert_eq!(errors[0].instance_path, "/theme");
+        assert_eq!(errors[0].layer.as_deref(), Some("config.toml"));
+        assert_eq!(errors[0].line, Some(1));
+
+        // sanity: a valid user file still passes once merged with defaults
+        validate_merged_config(
+            &json!({
+                "type": "object",
+                "properties": {
+                    "theme": { "type": "string" },
+                    "editor": scrolloff_schema()["properties"]["editor"].clone(),
+                },
+                "additionalProperties": false,
+            }),
+            defaults,
+            user,
+        )
+        .unwrap();
+    }
+}
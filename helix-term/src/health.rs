@@ -0,0 +1,58 @@
+//! Backs the config section of `hx --health`: re-runs the same schema
+//! validation [`crate::config::Config::load`] uses and returns every
+//! failure found as a plain list, so a user can fix their whole
+//! `config.toml` in one pass.
+
+use std::path::Path;
+
+use crate::config_validation::{validate_merged_config, ValidationError};
+
+/// Validate `user_config_path` against `schema`/`default_source` and return
+/// every failure found. A missing user config file validates cleanly — it's
+/// not an error for `hx --health` to report.
+pub fn check_config(
+    schema: &serde_json::Value,
+    default_source: &str,
+    user_config_path: &Path,
+) -> Vec<ValidationError> {
+    let user_source = std::fs::read_to_string(user_config_path).unwrap_or_default();
+
+    validate_merged_config(schema, default_source, &user_source)
+        .err()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": { "theme": { "type": "string" } },
+            "additionalProperties": false,
+        })
+    }
+
+    #[test]
+    fn check_config_reports_every_failure_not_just_the_first() {
+        let dir = std::env::temp_dir().join("helix-health-check-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let user_config_path = dir.join("config.toml");
+        std::fs::write(&user_config_path, "theme = 1\nunknown-key = 1\n").unwrap();
+
+        let errors = check_config(&schema(), "", &user_config_path);
+
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn check_config_treats_a_missing_user_config_as_empty() {
+        let user_config_path = std::env::temp_dir().join("helix-health-check-test-does-not-exist.toml");
+        let _ = std::fs::remove_file(&user_config_path);
+
+        let errors = check_config(&schema(), "theme = \"default\"\n", &user_config_path);
+
+        assert!(errors.is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeSet;
+
+use helix_core::syntax::config::Configuration as LanguageConfiguration;
+use helix_loader::grammar::get_language_names_from_grammars;
+
+/// Runtime values (themes, grammars, language servers, commands) that the
+/// generators turn into `enum` entries instead of plain `"type": "string"`.
+#[derive(Debug, Default)]
+pub struct SchemaParams {
+    pub theme_names: Vec<String>,
+    pub grammar_names: Vec<String>,
+    pub language_server_ids: Vec<String>,
+    pub command_names: Vec<String>,
+}
+
+impl SchemaParams {
+    /// Scan the runtime directories and the default language config to
+    /// collect the values a generated schema should offer as completions.
+    pub fn collect() -> Self {
+        Self {
+            theme_names: collect_theme_names(),
+            grammar_names: collect_grammar_names(),
+            language_server_ids: collect_language_server_ids(),
+            command_names: collect_command_names(),
+        }
+    }
+}
+
+/// Typable (`:`-prefixed) commands plus the static, keymap-only commands.
+fn collect_command_names() -> Vec<String> {
+    use helix_term::commands::{MappableCommand, TYPABLE_COMMAND_LIST};
+
+    let mut names: BTreeSet<String> = TYPABLE_COMMAND_LIST
+        .iter()
+        .map(|cmd| cmd.name.to_string())
+        .collect();
+
+    names.extend(
+        MappableCommand::STATIC_COMMAND_LIST
+            .iter()
+            .map(|cmd| cmd.name().to_string()),
+    );
+
+    names.into_iter().collect()
+}
+
+/// List the `.toml` theme files under every `runtime/themes` directory,
+/// plus the built-in themes bundled with the binary.
+fn collect_theme_names() -> Vec<String> {
+    let mut names: BTreeSet<String> = ["default", "base16_default"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+    for dir in helix_loader::runtime_dirs() {
+        let themes_dir = dir.join("themes");
+        let Ok(entries) = std::fs::read_dir(&themes_dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some("toml") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    names.insert(stem.to_string());
+                }
+            }
+        }
+    }
+
+    names.into_iter().collect()
+}
+
+/// Grammar names known to the default `languages.toml`.
+fn collect_grammar_names() -> Vec<String> {
+    get_language_names_from_grammars(default_language_config())
+}
+
+/// Language server ids declared under `[language-server.*]` in the default
+/// `languages.toml`.
+fn collect_language_server_ids() -> Vec<String> {
+    default_language_config()
+        .language_server
+        .keys()
+        .cloned()
+        .collect()
+}
+
+fn default_language_config() -> LanguageConfiguration {
+    helix_core::syntax::config::default_lang_config()
+        .expect("built-in languages.toml should always parse")
+}
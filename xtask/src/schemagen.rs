@@ -1,10 +1,118 @@
+use crate::schema_params::SchemaParams;
 use crate::DynError;
+use schemars::gen::SchemaSettings as SchemarsSettings;
 use serde_json::json;
 use std::fs;
 use std::path::PathBuf;
 
+/// Which JSON Schema dialect (and `$ref` style) a generated schema should
+/// target. `schema_for!` bakes in schemars' draft-07-ish default with
+/// `#/definitions/` refs; some tooling (e.g. anything that only speaks
+/// OpenAPI component schemas) needs a different flavor of the same schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaDialect {
+    /// schemars' default draft-07-ish output, `#/definitions/` refs.
+    Draft07,
+    /// OpenAPI 3's schema flavor: `option_nullable` instead of `null` type
+    /// unions, `#/components/schemas/` refs.
+    OpenApi3,
+}
+
+impl SchemaDialect {
+    fn parse(flag: &str) -> Result<Self, DynError> {
+        match flag {
+            "draft07" => Ok(Self::Draft07),
+            "openapi3" => Ok(Self::OpenApi3),
+            other => Err(format!("unknown schema dialect `{other}`, expected `draft07` or `openapi3`").into()),
+        }
+    }
+
+    fn into_generator(self) -> schemars::gen::SchemaGenerator {
+        match self {
+            Self::Draft07 => SchemarsSettings::default().into_generator(),
+            Self::OpenApi3 => SchemarsSettings::openapi3().into_generator(),
+        }
+    }
+
+    /// Where definitions live for this dialect, so the post-processing
+    /// helpers below can find them regardless of which dialect was picked.
+    fn definitions_mut<'a>(
+        self,
+        schema: &'a mut serde_json::Value,
+    ) -> Option<&'a mut serde_json::Map<String, serde_json::Value>> {
+        match self {
+            Self::Draft07 => schema.get_mut("definitions")?.as_object_mut(),
+            Self::OpenApi3 => schema
+                .get_mut("components")?
+                .get_mut("schemas")?
+                .as_object_mut(),
+        }
+    }
+
+    /// The `$ref` prefix used to point at a named definition in this dialect.
+    fn ref_prefix(self) -> &'static str {
+        match self {
+            Self::Draft07 => "#/definitions/",
+            Self::OpenApi3 => "#/components/schemas/",
+        }
+    }
+
+    /// Wrap a map of named schemas in the container this dialect expects at
+    /// the document root (`definitions` vs. `components.schemas`).
+    fn wrap_definitions(self, definitions: serde_json::Map<String, serde_json::Value>) -> serde_json::Value {
+        match self {
+            Self::Draft07 => json!({ "definitions": definitions }),
+            Self::OpenApi3 => json!({ "components": { "schemas": definitions } }),
+        }
+    }
+
+    /// The `$schema` meta-schema URI to stamp a hand-built document with.
+    /// OpenAPI 3 schemas aren't a standalone JSON Schema dialect with their
+    /// own `$schema` identifier — they're only ever valid embedded in an
+    /// OpenAPI document — so there's nothing correct to put here.
+    fn schema_uri(self) -> Option<&'static str> {
+        match self {
+            Self::Draft07 => Some("http://json-schema.org/draft-07/schema#"),
+            Self::OpenApi3 => None,
+        }
+    }
+}
+
+/// Generator configuration threaded through the three `generate_*_schema`
+/// functions so callers (e.g. the `--dialect` flag on `cargo xtask
+/// schema-generate`) can pick a target dialect without the generators
+/// needing to know about CLI parsing.
+pub struct SchemaSettings {
+    pub dialect: SchemaDialect,
+}
+
+impl Default for SchemaSettings {
+    fn default() -> Self {
+        Self {
+            dialect: SchemaDialect::Draft07,
+        }
+    }
+}
+
+impl SchemaSettings {
+    /// Parse a `--dialect <draft07|openapi3>` flag out of the xtask args
+    /// passed to `schema-generate`, defaulting to `draft07` if absent.
+    pub fn from_args(args: &[String]) -> Result<Self, DynError> {
+        let dialect = match args.iter().position(|a| a == "--dialect") {
+            Some(i) => {
+                let flag = args
+                    .get(i + 1)
+                    .ok_or("--dialect requires a value (draft07 or openapi3)")?;
+                SchemaDialect::parse(flag)?
+            }
+            None => SchemaDialect::Draft07,
+        };
+        Ok(Self { dialect })
+    }
+}
+
 /// Generate JSON schemas for Helix configuration files
-pub fn generate_schemas() -> Result<(), DynError> {
+pub fn generate_schemas(settings: &SchemaSettings) -> Result<(), DynError> {
     let schema_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .parent()
         .unwrap()
@@ -13,9 +121,13 @@ pub fn generate_schemas() -> Result<(), DynError> {
 
     fs::create_dir_all(&schema_dir)?;
 
+    // Collect the runtime values (themes, grammars, language servers) that
+    // the schemas below should offer as enums instead of plain strings.
+    let params = SchemaParams::collect();
+
     // Generate config.toml schema
     println!("Generating schema for config.toml...");
-    let config_schema = generate_config_schema();
+    let config_schema = generate_config_schema(settings, &params);
     let config_schema_json = serde_json::to_string_pretty(&config_schema)?;
     let config_schema_path = schema_dir.join("config.json");
     fs::write(&config_schema_path, config_schema_json)?;
@@ -23,7 +135,7 @@ pub fn generate_schemas() -> Result<(), DynError> {
 
     // Generate languages.toml schema (default - all fields as specified)
     println!("Generating schema for languages.toml (default)...");
-    let lang_schema = generate_languages_schema();
+    let lang_schema = generate_languages_schema(settings, &params);
     let lang_schema_json = serde_json::to_string_pretty(&lang_schema)?;
     let lang_schema_path = schema_dir.join("languages-default.json");
     fs::write(&lang_schema_path, lang_schema_json)?;
@@ -31,26 +143,36 @@ pub fn generate_schemas() -> Result<(), DynError> {
 
     // Generate languages.toml schema (user - most fields optional except name)
     println!("Generating schema for languages.toml (user overrides)...");
-    let lang_user_schema = generate_languages_user_schema();
+    let lang_user_schema = generate_languages_user_schema(settings, &params);
     let lang_user_schema_json = serde_json::to_string_pretty(&lang_user_schema)?;
     let lang_user_schema_path = schema_dir.join("languages-user.json");
     fs::write(&lang_user_schema_path, lang_user_schema_json)?;
     println!("  ✓ Written to {}", lang_user_schema_path.display());
 
+    // Generate the [keys] keymap schema
+    println!("Generating schema for keymaps...");
+    let keymap_schema = generate_keymap_schema(settings, &params);
+    let keymap_schema_json = serde_json::to_string_pretty(&keymap_schema)?;
+    let keymap_schema_path = schema_dir.join("keymap.json");
+    fs::write(&keymap_schema_path, keymap_schema_json)?;
+    println!("  ✓ Written to {}", keymap_schema_path.display());
+
     println!("\nSchema generation complete!");
     println!("\n  Schemas location: {}", schema_dir.display());
 
     Ok(())
 }
 
-fn generate_config_schema() -> serde_json::Value {
-    use schemars::schema_for;
-
+fn generate_config_schema(settings: &SchemaSettings, params: &SchemaParams) -> serde_json::Value {
     // Generate schema from the actual Rust type (ConfigRaw has the top-level structure)
-    let schema = schema_for!(helix_term::config::ConfigRaw);
+    let schema = settings
+        .dialect
+        .into_generator()
+        .into_root_schema_for::<helix_term::config::ConfigRaw>();
 
     // Convert to JSON value and add some metadata
     let mut schema_json = serde_json::to_value(schema).unwrap();
+    normalize_definitions_layout(&mut schema_json, settings.dialect);
     if let Some(obj) = schema_json.as_object_mut() {
         obj.insert("title".to_string(), json!("Helix Editor Configuration"));
         obj.insert(
@@ -59,20 +181,73 @@ fn generate_config_schema() -> serde_json::Value {
         );
     }
 
+    // Replace plain string fields with an enum of what's actually installed
+    // (theme names) so editors can autocomplete/validate against them.
+    set_enum_values(&mut schema_json, "theme", &params.theme_names);
+
+    // Describe the `[platform.<os>]` override sections merged in by
+    // `config_merge::merge_layered_config` before the root-level fields, so
+    // they validate with the same shape as the base config.
+    add_platform_overrides(&mut schema_json);
+
     // Ensure all objects have additionalProperties: false for strict validation
     ensure_no_additional_properties(&mut schema_json);
 
     schema_json
 }
 
-fn generate_languages_schema() -> serde_json::Value {
-    use schemars::schema_for;
+/// The per-OS override tables a `[platform.*]` section may define; keys
+/// correspond to `cfg!(target_os)` in `config_merge::current_platform_key`.
+const PLATFORM_KEYS: &[&str] = &["windows", "macos", "linux"];
+
+/// Add a `platform.<os>` property that mirrors the root schema's own
+/// properties, so `[platform.macos]` / `[platform.linux]` override tables
+/// accept (and validate) the same fields as `config.toml` itself.
+fn add_platform_overrides(schema_json: &mut serde_json::Value) {
+    let Some(root_properties) = schema_json.get("properties").cloned() else {
+        return;
+    };
+
+    let override_shape = json!({
+        "type": "object",
+        "properties": root_properties,
+        "additionalProperties": false,
+    });
 
+    let platform_properties: serde_json::Map<String, serde_json::Value> = PLATFORM_KEYS
+        .iter()
+        .map(|os| (os.to_string(), override_shape.clone()))
+        .collect();
+
+    if let Some(properties) = schema_json
+        .get_mut("properties")
+        .and_then(|p| p.as_object_mut())
+    {
+        properties.insert(
+            "platform".to_string(),
+            json!({
+                "type": "object",
+                "description": "Per-OS overrides merged over the base config before the user's own config.toml; keys match `cfg!(target_os)` (\"windows\", \"macos\", \"linux\").",
+                "properties": platform_properties,
+                "additionalProperties": false,
+            }),
+        );
+    }
+}
+
+fn generate_languages_schema(
+    settings: &SchemaSettings,
+    params: &SchemaParams,
+) -> serde_json::Value {
     // Generate schema from the actual Rust type
-    let schema = schema_for!(helix_core::syntax::config::Configuration);
+    let schema = settings
+        .dialect
+        .into_generator()
+        .into_root_schema_for::<helix_core::syntax::config::Configuration>();
 
     // Convert to JSON value and add some metadata
     let mut schema_json = serde_json::to_value(schema).unwrap();
+    normalize_definitions_layout(&mut schema_json, settings.dialect);
     if let Some(obj) = schema_json.as_object_mut() {
         obj.insert(
             "title".to_string(),
@@ -85,7 +260,28 @@ fn generate_languages_schema() -> serde_json::Value {
     }
 
     // Add serde aliases as actual properties (JSON Schema doesn't have aliases)
-    add_serde_aliases(&mut schema_json);
+    add_serde_aliases(&mut schema_json, settings.dialect);
+
+    // Constrain grammar/language-server references to what's actually
+    // declared in the default languages.toml. `grammar` lives directly on
+    // `LanguageConfiguration`; the `language-servers = [...]` list is made up
+    // of `LanguageServerFeatures` entries, each identified by its own `name`
+    // field — not the per-id `LanguageServerConfiguration` block, which has
+    // no `name` property at all.
+    set_definition_enum_values(
+        &mut schema_json,
+        settings.dialect,
+        "LanguageConfiguration",
+        "grammar",
+        &params.grammar_names,
+    );
+    set_definition_enum_values(
+        &mut schema_json,
+        settings.dialect,
+        "LanguageServerFeatures",
+        "name",
+        &params.language_server_ids,
+    );
 
     // Ensure all objects have additionalProperties: false for strict validation
     ensure_no_additional_properties(&mut schema_json);
@@ -93,20 +289,22 @@ fn generate_languages_schema() -> serde_json::Value {
     schema_json
 }
 
-fn generate_languages_user_schema() -> serde_json::Value {
-    use schemars::schema_for;
-
+fn generate_languages_user_schema(
+    settings: &SchemaSettings,
+    params: &SchemaParams,
+) -> serde_json::Value {
     // Generate schema from the actual Rust type
-    let schema = schema_for!(helix_core::syntax::config::Configuration);
+    let schema = settings
+        .dialect
+        .into_generator()
+        .into_root_schema_for::<helix_core::syntax::config::Configuration>();
 
     // Convert to JSON value
     let mut schema_json = serde_json::to_value(schema).unwrap();
+    normalize_definitions_layout(&mut schema_json, settings.dialect);
 
     // Modify the schema to make all fields optional except "name" in LanguageConfiguration
-    if let Some(definitions) = schema_json
-        .get_mut("definitions")
-        .and_then(|d| d.as_object_mut())
-    {
+    if let Some(definitions) = settings.dialect.definitions_mut(&mut schema_json) {
         // Find LanguageConfiguration definition
         if let Some(lang_config) = definitions
             .get_mut("LanguageConfiguration")
@@ -144,7 +342,26 @@ fn generate_languages_user_schema() -> serde_json::Value {
     }
 
     // Add serde aliases as actual properties (JSON Schema doesn't have aliases)
-    add_serde_aliases(&mut schema_json);
+    add_serde_aliases(&mut schema_json, settings.dialect);
+
+    // Constrain grammar/language-server references to what's actually
+    // declared in the default languages.toml. See the comment in
+    // `generate_languages_schema` for why this targets
+    // `LanguageServerFeatures.name` rather than `LanguageServerConfiguration`.
+    set_definition_enum_values(
+        &mut schema_json,
+        settings.dialect,
+        "LanguageConfiguration",
+        "grammar",
+        &params.grammar_names,
+    );
+    set_definition_enum_values(
+        &mut schema_json,
+        settings.dialect,
+        "LanguageServerFeatures",
+        "name",
+        &params.language_server_ids,
+    );
 
     // Ensure all objects have additionalProperties: false for strict validation
     ensure_no_additional_properties(&mut schema_json);
@@ -152,13 +369,52 @@ fn generate_languages_user_schema() -> serde_json::Value {
     schema_json
 }
 
+/// Generate a schema for the `[keys]` section of config.toml. Unlike the
+/// other schemas, this one isn't derived from a Rust type with
+/// `schema_for!` — a keymap is a recursively-nested map of key names to
+/// either a command name or another keymap ("sticky" multi-key sequences),
+/// so we build the `KeymapNode` definition by hand and constrain its leaf
+/// values to the live command registry.
+fn generate_keymap_schema(settings: &SchemaSettings, params: &SchemaParams) -> serde_json::Value {
+    let dialect = settings.dialect;
+    let keymap_node_ref = json!({ "$ref": format!("{}KeymapNode", dialect.ref_prefix()) });
+
+    let mut definitions = serde_json::Map::new();
+    definitions.insert(
+        "KeymapNode".to_string(),
+        json!({
+            "description": "A key binding: a single command, a list of commands run in sequence, or a nested keymap for sticky multi-key sequences.",
+            "anyOf": [
+                { "type": "string", "enum": params.command_names },
+                { "type": "array", "items": { "type": "string", "enum": params.command_names } },
+                { "type": "object", "additionalProperties": keymap_node_ref },
+            ],
+        }),
+    );
+
+    let mut schema_json = dialect.wrap_definitions(definitions);
+    if let Some(obj) = schema_json.as_object_mut() {
+        if let Some(schema_uri) = dialect.schema_uri() {
+            obj.insert("$schema".to_string(), json!(schema_uri));
+        }
+        obj.insert("title".to_string(), json!("Helix Keymap"));
+        obj.insert(
+            "description".to_string(),
+            json!("Schema for the [keys] section of config.toml"),
+        );
+        obj.insert(
+            "$ref".to_string(),
+            json!(format!("{}KeymapNode", dialect.ref_prefix())),
+        );
+    }
+
+    schema_json
+}
+
 /// Add serde field aliases as actual properties in the schema
 /// This is needed because JSON Schema doesn't have a concept of aliases
-fn add_serde_aliases(schema: &mut serde_json::Value) {
-    if let Some(definitions) = schema
-        .get_mut("definitions")
-        .and_then(|d| d.as_object_mut())
-    {
+fn add_serde_aliases(schema: &mut serde_json::Value, dialect: SchemaDialect) {
+    if let Some(definitions) = dialect.definitions_mut(schema) {
         // Add comment-token as an alias for comment-tokens in LanguageConfiguration
         if let Some(lang_config) = definitions
             .get_mut("LanguageConfiguration")
@@ -177,6 +433,61 @@ fn add_serde_aliases(schema: &mut serde_json::Value) {
     }
 }
 
+/// Set a root-level property's `enum` to the given values, e.g. constraining
+/// `theme` in the top-level config schema to the installed theme names.
+fn set_enum_values(schema: &mut serde_json::Value, property: &str, values: &[String]) {
+    if let Some(prop) = schema
+        .get_mut("properties")
+        .and_then(|p| p.as_object_mut())
+        .and_then(|p| p.get_mut(property))
+    {
+        prop.as_object_mut()
+            .unwrap()
+            .insert("enum".to_string(), json!(values));
+    }
+}
+
+/// Set a property's `enum` to the given values inside one definition entry
+/// (`#/definitions/...` or `#/components/schemas/...` depending on dialect),
+/// e.g. constraining `LanguageConfiguration.grammar` to the grammar names
+/// `SchemaParams::collect` gathers from the default languages.toml.
+fn set_definition_enum_values(
+    schema: &mut serde_json::Value,
+    dialect: SchemaDialect,
+    definition: &str,
+    property: &str,
+    values: &[String],
+) {
+    if let Some(prop) = dialect
+        .definitions_mut(schema)
+        .and_then(|d| d.get_mut(definition))
+        .and_then(|def| def.get_mut("properties"))
+        .and_then(|p| p.as_object_mut())
+        .and_then(|p| p.get_mut(property))
+    {
+        prop.as_object_mut()
+            .unwrap()
+            .insert("enum".to_string(), json!(values));
+    }
+}
+
+/// `SchemaSettings::openapi3()` only changes the `$ref` *string* schemars
+/// writes (to `#/components/schemas/...`); the generator still serializes
+/// the actual schemas under the top-level `definitions` key, same as the
+/// draft-07 settings. Physically move them so those refs resolve inside
+/// the emitted document instead of dangling.
+fn normalize_definitions_layout(schema: &mut serde_json::Value, dialect: SchemaDialect) {
+    if dialect != SchemaDialect::OpenApi3 {
+        return;
+    }
+    let Some(obj) = schema.as_object_mut() else {
+        return;
+    };
+    if let Some(definitions) = obj.remove("definitions") {
+        obj.insert("components".to_string(), json!({ "schemas": definitions }));
+    }
+}
+
 /// Recursively ensure all objects in the schema have additionalProperties: false
 fn ensure_no_additional_properties(value: &mut serde_json::Value) {
     match value {
@@ -205,3 +516,122 @@ fn ensure_no_additional_properties(value: &mut serde_json::Value) {
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_params() -> SchemaParams {
+        SchemaParams {
+            theme_names: vec!["default".to_string()],
+            grammar_names: vec!["rust".to_string()],
+            language_server_ids: vec!["rust-analyzer".to_string()],
+            command_names: vec!["quit".to_string()],
+        }
+    }
+
+    fn compiles(schema: &serde_json::Value) {
+        jsonschema::JSONSchema::options()
+            .with_draft(jsonschema::Draft::Draft202012)
+            .compile(schema)
+            .unwrap_or_else(|err| panic!("schema does not compile: {err}\n{schema:#}"));
+    }
+
+    #[test]
+    fn openapi3_refs_resolve_inside_the_emitted_document() {
+        let settings = SchemaSettings {
+            dialect: SchemaDialect::OpenApi3,
+        };
+        let params = test_params();
+
+        compiles(&generate_config_schema(&settings, &params));
+        compiles(&generate_languages_schema(&settings, &params));
+        compiles(&generate_languages_user_schema(&settings, &params));
+        compiles(&generate_keymap_schema(&settings, &params));
+    }
+
+    #[test]
+    fn draft07_refs_still_resolve() {
+        let settings = SchemaSettings {
+            dialect: SchemaDialect::Draft07,
+        };
+        let params = test_params();
+
+        compiles(&generate_config_schema(&settings, &params));
+        compiles(&generate_languages_schema(&settings, &params));
+        compiles(&generate_languages_user_schema(&settings, &params));
+        compiles(&generate_keymap_schema(&settings, &params));
+    }
+
+    #[test]
+    fn from_args_defaults_to_draft07_when_the_flag_is_absent() {
+        let settings = SchemaSettings::from_args(&[]).unwrap();
+        assert_eq!(settings.dialect, SchemaDialect::Draft07);
+    }
+
+    #[test]
+    fn from_args_parses_a_valid_dialect_flag() {
+        let args = vec!["--dialect".to_string(), "openapi3".to_string()];
+        let settings = SchemaSettings::from_args(&args).unwrap();
+        assert_eq!(settings.dialect, SchemaDialect::OpenApi3);
+    }
+
+    #[test]
+    fn from_args_rejects_a_missing_value() {
+        let args = vec!["--dialect".to_string()];
+        assert!(SchemaSettings::from_args(&args).is_err());
+    }
+
+    #[test]
+    fn from_args_rejects_an_unknown_dialect() {
+        let args = vec!["--dialect".to_string(), "yaml-ish".to_string()];
+        assert!(SchemaSettings::from_args(&args).is_err());
+    }
+
+    #[test]
+    fn keymap_schema_stamps_schema_uri_per_dialect() {
+        let draft07 = SchemaSettings {
+            dialect: SchemaDialect::Draft07,
+        };
+        let openapi3 = SchemaSettings {
+            dialect: SchemaDialect::OpenApi3,
+        };
+        let params = test_params();
+
+        assert_eq!(
+            generate_keymap_schema(&draft07, &params)["$schema"].as_str(),
+            Some("http://json-schema.org/draft-07/schema#"),
+        );
+        assert!(generate_keymap_schema(&openapi3, &params).get("$schema").is_none());
+    }
+
+    #[test]
+    fn language_servers_array_items_get_the_name_enum() {
+        let settings = SchemaSettings::default();
+        let params = test_params();
+        let schema = generate_languages_schema(&settings, &params);
+
+        let name_prop = &schema["definitions"]["LanguageServerFeatures"]["properties"]["name"];
+        assert_eq!(
+            name_prop["enum"].as_array().expect("name should have an enum"),
+            &[json!("rust-analyzer")],
+        );
+
+        // `LanguageServerConfiguration` (the per-id `[language-server.<id>]`
+        // block) has no `name` property to enum-ify, so it must be untouched.
+        assert!(schema["definitions"]["LanguageServerConfiguration"]["properties"]["name"].is_null());
+    }
+
+    #[test]
+    fn openapi3_languages_user_schema_only_requires_name() {
+        let settings = SchemaSettings {
+            dialect: SchemaDialect::OpenApi3,
+        };
+        let schema = generate_languages_user_schema(&settings, &test_params());
+
+        let required = schema["components"]["schemas"]["LanguageConfiguration"]["required"]
+            .as_array()
+            .expect("LanguageConfiguration should still be present under components.schemas");
+        assert_eq!(required, &[json!("name")]);
+    }
+}